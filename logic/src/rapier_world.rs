@@ -1,46 +1,222 @@
 use crate::utils::NodeExt;
-use gdnative::api::Engine;
+use crossbeam::channel::{unbounded, Receiver};
+use gdnative::api::{Engine, Sprite};
 use gdnative::prelude::*;
+use rand::Rng;
 use rapier2d::{
     dynamics::{
-        CCDSolver, IntegrationParameters, JointSet, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+        BallJoint, CCDSolver, FixedJoint, IntegrationParameters, IslandManager, JointHandle,
+        JointSet, PrismaticJoint, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
     },
-    geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase},
+    geometry::{
+        ActiveEvents, BroadPhase, ColliderBuilder, ColliderHandle, ColliderSet, CollisionEvent,
+        NarrowPhase,
+    },
+    math::Isometry,
     na,
-    pipeline::PhysicsPipeline,
+    pipeline::{ChannelEventCollector, ContactForceEvent, PhysicsPipeline},
 };
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Mirrors `bevy_rapier`'s `TimestepMode`.
+#[derive(Copy, Clone, Debug, ToVariant, FromVariant)]
+pub enum TimestepMode {
+    Variable,
+    Fixed { dt: f32, substeps: u32 },
+    /// Blends the rendered transform between the previous and current
+    /// physics position using the leftover accumulator.
+    Interpolated { dt: f32, time_scale: f32 },
+}
+
+impl Default for TimestepMode {
+    fn default() -> Self {
+        TimestepMode::Fixed {
+            dt: 1. / 60.,
+            substeps: 1,
+        }
+    }
+}
+
+// `TimestepMode` carries per-variant data (`dt`, `substeps`, ...), so it
+// round-trips through GDScript as a Dictionary via `ToVariant`/`FromVariant`
+// rather than a simple int-backed enum — there's no int hint that applies.
+impl Export for TimestepMode {
+    type Hint = ();
+
+    fn export_info(_hint: Option<Self::Hint>) -> ExportInfo {
+        ExportInfo::new(VariantType::Dictionary)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Interpolation {
+    previous: Isometry<f32>,
+    current: Isometry<f32>,
+}
+
+impl Interpolation {
+    fn at_rest(pos: Isometry<f32>) -> Self {
+        Self {
+            previous: pos,
+            current: pos,
+        }
+    }
+}
+
+/// Collider geometry accepted by `PhysicsState::spawn_body`.
+enum Shape {
+    Ball {
+        radius: f32,
+    },
+    Cuboid {
+        half_extents: na::Vector2<f32>,
+    },
+    Capsule {
+        half_height: f32,
+        radius: f32,
+    },
+    ConvexPolygon {
+        points: Vec<na::Point2<f32>>,
+    },
+}
+
+/// Per-body material and dynamics tuning, passed from GDScript as a Dictionary.
+#[derive(Copy, Clone, Debug, ToVariant, FromVariant)]
+pub struct BodyMaterial {
+    pub friction: f32,
+    pub restitution: f32,
+    pub density: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    pub gravity_scale: f32,
+    pub dominance_group: i8,
+    pub lock_translation: bool,
+    pub lock_rotation: bool,
+    pub ccd_enabled: bool,
+}
+
+impl Default for BodyMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 0.5,
+            restitution: 0.,
+            density: 1.,
+            linear_damping: 0.,
+            angular_damping: 0.,
+            gravity_scale: 1.,
+            dominance_group: 0,
+            lock_translation: false,
+            lock_rotation: false,
+            ccd_enabled: false,
+        }
+    }
+}
+
+/// Borrows rather than clones, so field order/names must match `PhysicsSnapshot`.
+#[derive(Serialize)]
+struct PhysicsSnapshotRef<'a> {
+    island_manager: &'a IslandManager,
+    broad_phase: &'a BroadPhase,
+    narrow_phase: &'a NarrowPhase,
+    bodies: &'a RigidBodySet,
+    colliders: &'a ColliderSet,
+    joints: &'a JointSet,
+    ccd: &'a CCDSolver,
+    accumulator: f32,
+}
+
+#[derive(Deserialize)]
+struct PhysicsSnapshot {
+    island_manager: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    joints: JointSet,
+    ccd: CCDSolver,
+    accumulator: f32,
+}
+
+/// Node names stand in for `Ref<Node2D>` since scene nodes aren't serializable.
+#[derive(Serialize)]
+struct WorldSnapshotRef<'a> {
+    physics: PhysicsSnapshotRef<'a>,
+    boxes: Vec<Option<(RigidBodyHandle, String)>>,
+    /// Must round-trip alongside `physics.joints`: Rapier's generational
+    /// slots get reused, so restoring the `JointSet` without this table
+    /// risks `remove_joint(index)` later tearing down an unrelated joint.
+    joints: Vec<Option<JointHandle>>,
+}
+
+#[derive(Deserialize)]
+struct WorldSnapshot {
+    physics: PhysicsSnapshot,
+    boxes: Vec<Option<(RigidBodyHandle, String)>>,
+    joints: Vec<Option<JointHandle>>,
+}
 
 struct PhysicsState {
     pub pipeline: PhysicsPipeline,
+    pub island_manager: IslandManager,
     pub broad_phase: BroadPhase,
     pub narrow_phase: NarrowPhase,
     pub bodies: RigidBodySet,
     pub colliders: ColliderSet,
     pub joints: JointSet,
     pub ccd: CCDSolver,
+    interpolation: HashMap<RigidBodyHandle, Interpolation>,
+    /// Leftover render time that hasn't produced a physics step yet.
+    accumulator: f32,
+    event_collector: ChannelEventCollector,
+    collision_events: Receiver<CollisionEvent>,
+    contact_force_events: Receiver<ContactForceEvent>,
 }
 
 impl PhysicsState {
     fn new() -> Self {
+        let (collision_send, collision_events) = unbounded();
+        let (contact_force_send, contact_force_events) = unbounded();
+
         Self {
             pipeline: PhysicsPipeline::new(),
+            island_manager: IslandManager::new(),
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             bodies: RigidBodySet::new(),
             colliders: ColliderSet::new(),
             joints: JointSet::new(),
             ccd: CCDSolver::new(),
+            interpolation: HashMap::new(),
+            accumulator: 0.,
+            event_collector: ChannelEventCollector::new(collision_send, contact_force_send),
+            collision_events,
+            contact_force_events,
         }
     }
 
-    fn tick(&mut self, gravity: Vector2) {
+    /// Runs a single physics step of `dt` seconds and records each body's
+    /// previous/current position for interpolation.
+    fn step(&mut self, gravity: Vector2, dt: f32) {
         let gravity = na::Vector2::new(gravity.x, gravity.y);
-        let integration_parameters = IntegrationParameters::default();
+        let integration_parameters = IntegrationParameters {
+            dt,
+            ..Default::default()
+        };
+
+        for (handle, body) in self.bodies.iter() {
+            let entry = self
+                .interpolation
+                .entry(handle)
+                .or_insert_with(|| Interpolation::at_rest(*body.position()));
+            entry.previous = entry.current;
+        }
 
         self.pipeline.step(
             &gravity,
             &integration_parameters,
+            &mut self.island_manager,
             &mut self.broad_phase,
             &mut self.narrow_phase,
             &mut self.bodies,
@@ -48,8 +224,127 @@ impl PhysicsState {
             &mut self.joints,
             &mut self.ccd,
             &(),
-            &(),
+            &self.event_collector,
         );
+
+        for (handle, body) in self.bodies.iter() {
+            if let Some(entry) = self.interpolation.get_mut(&handle) {
+                entry.current = *body.position();
+            }
+        }
+    }
+
+    /// Returns `false` if `handle` no longer exists (e.g. already despawned).
+    fn despawn(&mut self, handle: RigidBodyHandle) -> bool {
+        self.interpolation.remove(&handle);
+
+        self.bodies
+            .remove(
+                handle,
+                &mut self.island_manager,
+                &mut self.colliders,
+                &mut self.joints,
+            )
+            .is_some()
+    }
+
+    /// Number of bodies the solver is actively simulating, i.e. not asleep.
+    fn active_body_count(&self) -> usize {
+        self.bodies.iter().filter(|(_, body)| !body.is_sleeping()).count()
+    }
+
+    fn collider_parent(&self, handle: ColliderHandle) -> Option<RigidBodyHandle> {
+        self.colliders.get(handle).and_then(|collider| collider.parent())
+    }
+
+    fn drain_collision_events(&self) -> impl Iterator<Item = CollisionEvent> + '_ {
+        self.collision_events.try_iter()
+    }
+
+    fn drain_contact_force_events(&self) -> impl Iterator<Item = ContactForceEvent> + '_ {
+        self.contact_force_events.try_iter()
+    }
+
+    /// `pipeline`, `interpolation` and the event channels are excluded:
+    /// they hold no state that affects where a future `step` puts a body.
+    fn snapshot(&self) -> PhysicsSnapshotRef<'_> {
+        PhysicsSnapshotRef {
+            island_manager: &self.island_manager,
+            broad_phase: &self.broad_phase,
+            narrow_phase: &self.narrow_phase,
+            bodies: &self.bodies,
+            colliders: &self.colliders,
+            joints: &self.joints,
+            ccd: &self.ccd,
+            accumulator: self.accumulator,
+        }
+    }
+
+    fn restore(&mut self, snapshot: PhysicsSnapshot) {
+        self.island_manager = snapshot.island_manager;
+        self.broad_phase = snapshot.broad_phase;
+        self.narrow_phase = snapshot.narrow_phase;
+        self.bodies = snapshot.bodies;
+        self.colliders = snapshot.colliders;
+        self.joints = snapshot.joints;
+        self.ccd = snapshot.ccd;
+        self.accumulator = snapshot.accumulator;
+        self.interpolation.clear();
+    }
+
+    /// Advances the simulation by `delta` seconds of render time according to
+    /// `mode`, returning the interpolation factor (`accumulator / dt`) to use
+    /// when blending rendered transforms, or `1.0` when no blending applies.
+    fn tick(&mut self, gravity: Vector2, delta: f32, mode: TimestepMode) -> f32 {
+        match mode {
+            TimestepMode::Variable => {
+                self.step(gravity, delta);
+                1.
+            }
+            TimestepMode::Fixed { dt, substeps } => {
+                self.accumulator += delta;
+
+                while self.accumulator >= dt {
+                    let substep_dt = dt / substeps.max(1) as f32;
+                    for _ in 0..substeps.max(1) {
+                        self.step(gravity, substep_dt);
+                    }
+                    self.accumulator -= dt;
+                }
+
+                1.
+            }
+            TimestepMode::Interpolated { dt, time_scale } => {
+                self.accumulator += delta * time_scale;
+
+                while self.accumulator >= dt {
+                    self.step(gravity, dt);
+                    self.accumulator -= dt;
+                }
+
+                self.accumulator / dt
+            }
+        }
+    }
+
+    /// Returns the interpolated position to render for `handle`, blending
+    /// between its previous and current physics position using `factor` in
+    /// `Interpolated` mode, or its current position otherwise.
+    fn render_position(&self, handle: RigidBodyHandle, factor: f32) -> Option<Isometry<f32>> {
+        let entry = self.interpolation.get(&handle)?;
+        let factor = factor.clamp(0., 1.);
+
+        let translation = entry
+            .previous
+            .translation
+            .vector
+            .lerp(&entry.current.translation.vector, factor);
+        let rotation = entry
+            .previous
+            .rotation
+            .nlerp(&entry.current.rotation, factor);
+
+        Some(Isometry::from_parts(translation.into(), rotation))
     }
 
     fn add_static(&mut self, x: f32, y: f32, w: f32, h: f32) {
@@ -57,31 +352,192 @@ impl PhysicsState {
 
         let floor = self.bodies.insert(floor);
 
-        let floor_collider = ColliderBuilder::cuboid(w, h).build();
+        let floor_collider = ColliderBuilder::cuboid(w, h)
+            .active_events(ActiveEvents::COLLISION_EVENTS)
+            .build();
 
         self.colliders
             .insert(floor_collider, floor, &mut self.bodies);
     }
 
-    fn add_box(&mut self, x: f32, y: f32) -> RigidBodyHandle {
-        let falling_box = RigidBodyBuilder::new_dynamic().translation(x, y).build();
-        let falling_box = self.bodies.insert(falling_box);
+    /// Returns `None` if `shape` is a degenerate `ConvexPolygon` Rapier can't hull.
+    fn spawn_body(
+        &mut self,
+        x: f32,
+        y: f32,
+        shape: Shape,
+        material: BodyMaterial,
+        contact_force_threshold: Option<f32>,
+    ) -> Option<RigidBodyHandle> {
+        // Build the collider before inserting the body: `convex_hull` can
+        // fail on a degenerate `ConvexPolygon`, and bailing out after the
+        // body is already in `self.bodies` would leak a colliderless,
+        // untracked body into the simulation forever.
+        let mut active_events = ActiveEvents::COLLISION_EVENTS;
+        if contact_force_threshold.is_some() {
+            active_events |= ActiveEvents::CONTACT_FORCE_EVENTS;
+        }
 
-        let box_collider = ColliderBuilder::cuboid(48. * 0.4, 48. * 0.4).build();
-        self.colliders
-            .insert(box_collider, falling_box, &mut self.bodies);
+        let collider_builder = match shape {
+            Shape::Ball { radius } => ColliderBuilder::ball(radius),
+            Shape::Cuboid { half_extents } => {
+                ColliderBuilder::cuboid(half_extents.x, half_extents.y)
+            }
+            Shape::Capsule { half_height, radius } => {
+                ColliderBuilder::capsule_y(half_height, radius)
+            }
+            Shape::ConvexPolygon { points } => ColliderBuilder::convex_hull(&points)?,
+        };
+
+        let mut collider = collider_builder
+            .friction(material.friction)
+            .restitution(material.restitution)
+            .density(material.density)
+            .active_events(active_events)
+            .build();
+
+        if let Some(threshold) = contact_force_threshold {
+            collider.set_contact_force_event_threshold(threshold);
+        }
+
+        let mut body_builder = RigidBodyBuilder::new_dynamic()
+            .translation(x, y)
+            .linear_damping(material.linear_damping)
+            .angular_damping(material.angular_damping)
+            .gravity_scale(material.gravity_scale)
+            .dominance_group(material.dominance_group)
+            .ccd_enabled(material.ccd_enabled);
+
+        if material.lock_translation {
+            body_builder = body_builder.lock_translations();
+        }
+        if material.lock_rotation {
+            body_builder = body_builder.lock_rotations();
+        }
+
+        let body = self.bodies.insert(body_builder.build());
+        self.colliders.insert(collider, body, &mut self.bodies);
+
+        Some(body)
+    }
 
-        falling_box
+    /// Rapier's `BallJoint` is the 2D equivalent of a revolute joint.
+    fn add_revolute_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        anchor1: na::Point2<f32>,
+        anchor2: na::Point2<f32>,
+    ) -> JointHandle {
+        let joint = BallJoint::new(anchor1, anchor2);
+
+        self.joints.insert(&mut self.bodies, body1, body2, joint)
+    }
+
+    fn add_fixed_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        frame1: Isometry<f32>,
+        frame2: Isometry<f32>,
+    ) -> JointHandle {
+        let joint = FixedJoint::new(frame1, frame2);
+
+        self.joints.insert(&mut self.bodies, body1, body2, joint)
+    }
+
+    fn add_prismatic_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        anchor1: na::Point2<f32>,
+        axis1: na::Unit<na::Vector2<f32>>,
+        anchor2: na::Point2<f32>,
+        axis2: na::Unit<na::Vector2<f32>>,
+    ) -> JointHandle {
+        let joint = PrismaticJoint::new(anchor1, axis1, anchor2, axis2);
+
+        self.joints.insert(&mut self.bodies, body1, body2, joint)
+    }
+
+    fn remove_joint(&mut self, handle: JointHandle) {
+        self.joints.remove(handle, &mut self.bodies, true);
     }
+
+    /// Unlike `spawn_body`, the collider is always a tiny sensor ball.
+    fn spawn_effect_body(
+        &mut self,
+        x: f32,
+        y: f32,
+        linvel: na::Vector2<f32>,
+        angvel: f32,
+    ) -> RigidBodyHandle {
+        let body = RigidBodyBuilder::new_dynamic()
+            .translation(x, y)
+            .linvel(linvel.x, linvel.y)
+            .angvel(angvel)
+            .gravity_scale(0.2)
+            .build();
+
+        let body = self.bodies.insert(body);
+
+        let collider = ColliderBuilder::ball(2.).sensor(true).build();
+        self.colliders.insert(collider, body, &mut self.bodies);
+
+        body
+    }
+}
+
+/// A short-lived visual effect spawned at a high-impulse contact.
+struct Effect {
+    body: RigidBodyHandle,
+    node: Ref<Sprite>,
+    frame_durations: Vec<f32>,
+    frame_index: usize,
+    frame_elapsed: f32,
+    age: f32,
+    lifetime: f32,
 }
 
 #[derive(NativeClass)]
 #[inherit(Node2D)]
+#[register_with(Self::register_signals)]
 pub struct RapierWorld2D {
     #[property]
     gravity: Vector2,
+    #[property]
+    timestep_mode: TimestepMode,
+    /// `0.0` disables contact-force reporting for boxes.
+    #[property]
+    contact_force_threshold: f32,
+    /// Despawns any box whose position leaves the viewport rect computed in `_ready`.
+    #[property]
+    auto_cull: bool,
+    /// `0.0` (the default) disables auto-spawned effects entirely.
+    #[property]
+    effect_impulse_threshold: f32,
+    /// Empty falls back to a single frame held for the whole `effect_lifetime`.
+    #[property]
+    effect_frame_durations: Float32Array,
+    #[property]
+    effect_lifetime: f32,
+    #[property]
+    effect_speed_min: f32,
+    #[property]
+    effect_speed_max: f32,
+    /// Half-angle (degrees) of the cone an auto-spawned effect's initial
+    /// velocity is randomized within, centered on the contact's force direction.
+    #[property]
+    effect_spread_degrees: f32,
     physics: RefCell<PhysicsState>,
-    boxes: RefCell<Vec<(RigidBodyHandle, Ref<Node2D>)>>,
+    /// `None` marks a despawned box so earlier indices stay valid.
+    boxes: RefCell<Vec<Option<(RigidBodyHandle, Ref<Node2D>)>>>,
+    /// Indexed the same way as `boxes`; `None` marks a removed joint.
+    joints: RefCell<Vec<Option<JointHandle>>>,
+    /// Kept separate from `boxes` since effects aren't serialized by `save_state`.
+    effects: RefCell<Vec<Option<Effect>>>,
+    /// The play area computed in `_ready`, used by `auto_cull`.
+    viewport_rect: RefCell<Option<Rect2>>,
 }
 
 #[methods]
@@ -91,18 +547,51 @@ impl RapierWorld2D {
 
         Self {
             gravity: Vector2::new(0., 98.),
+            timestep_mode: TimestepMode::default(),
+            contact_force_threshold: 0.,
+            auto_cull: false,
+            effect_impulse_threshold: 0.,
+            effect_frame_durations: Float32Array::new(),
+            effect_lifetime: 0.4,
+            effect_speed_min: 20.,
+            effect_speed_max: 80.,
+            effect_spread_degrees: 30.,
             physics: RefCell::new(PhysicsState::new()),
             boxes: RefCell::new(Vec::new()),
+            joints: RefCell::new(Vec::new()),
+            effects: RefCell::new(Vec::new()),
+            viewport_rect: RefCell::new(None),
         }
     }
 
+    fn register_signals(builder: &ClassBuilder<Self>) {
+        builder
+            .signal("collision_started")
+            .with_param("body_a", VariantType::Object)
+            .with_param("body_b", VariantType::Object)
+            .done();
+        builder
+            .signal("collision_stopped")
+            .with_param("body_a", VariantType::Object)
+            .with_param("body_b", VariantType::Object)
+            .done();
+        builder
+            .signal("contact_force")
+            .with_param("body", VariantType::Object)
+            .with_param("magnitude", VariantType::F64)
+            .done();
+    }
+
     #[export]
     fn _ready(&self, owner: &Node2D) {
-        let w = owner.get_viewport_rect().width();
-        let h = owner.get_viewport_rect().height();
+        let viewport_rect = owner.get_viewport_rect();
+        let w = viewport_rect.width();
+        let h = viewport_rect.height();
 
         godot_print!("size: {},{}", w, h);
 
+        *self.viewport_rect.borrow_mut() = Some(viewport_rect);
+
         let mut physics = self.physics.borrow_mut();
         physics.add_static(0., h, w, 10.);
         physics.add_static(0., h / 2., 10., h);
@@ -118,7 +607,7 @@ impl RapierWorld2D {
     }
 
     #[export]
-    fn _process(&self, owner: &Node2D, _delta: f64) {
+    fn _process(&self, owner: &Node2D, delta: f64) {
         let mouse_press = Input::godot_singleton().is_action_pressed("click");
         let pos = owner.get_global_mouse_position();
 
@@ -130,24 +619,256 @@ impl RapierWorld2D {
 
         if mouse_press {
             self.spawn(owner, pos.x, pos.y);
-            let count = self.boxes.borrow().len();
-            let label: TRef<Label> = owner.get_typed_node("../Label");
-            label.set_text(format!("boxes: {}", count));
         }
 
-        self.physics.borrow_mut().tick(self.gravity);
+        let factor = self
+            .physics
+            .borrow_mut()
+            .tick(self.gravity, delta as f32, self.timestep_mode);
+
+        self.emit_physics_signals(owner);
+        self.update_boxes(owner, factor);
+        self.tick_effects(delta as f32);
+
+        if self.auto_cull {
+            self.cull_offscreen_boxes(owner);
+        }
 
-        self.update_boxes(owner);
+        let count = self.boxes.borrow().iter().flatten().count();
+        let active = self.physics.borrow().active_body_count();
+        let label: TRef<Label> = owner.get_typed_node("../Label");
+        label.set_text(format!("boxes: {} (active: {})", count, active));
     }
 
-    fn update_boxes(&self, _owner: &Node2D) {
-        let bodies = &self.physics.borrow().bodies;
+    fn emit_physics_signals(&self, owner: &Node2D) {
+        let mut impact_effects = Vec::new();
+
+        {
+            let physics = self.physics.borrow();
+
+            for event in physics.drain_collision_events() {
+                let (collider_a, collider_b, signal) = match event {
+                    CollisionEvent::Started(a, b, _) => (a, b, "collision_started"),
+                    CollisionEvent::Stopped(a, b, _) => (a, b, "collision_stopped"),
+                };
 
-        for b in self.boxes.borrow().iter() {
-            let handle = b.0;
-            let node = b.1;
-            let body = bodies.get(handle).unwrap();
-            let pos = body.position();
+                let body_a = physics
+                    .collider_parent(collider_a)
+                    .and_then(|handle| self.node_for_body(handle));
+                let body_b = physics
+                    .collider_parent(collider_b)
+                    .and_then(|handle| self.node_for_body(handle));
+
+                // Static bodies (the floor/walls from `add_static`) aren't
+                // tracked in `boxes`, so `body_a`/`body_b` can be `None` even
+                // for a real collision; emit with a null side rather than
+                // dropping every box-vs-floor/wall event.
+                if body_a.is_some() || body_b.is_some() {
+                    let variant_a = body_a.map(|b| b.to_variant()).unwrap_or_else(Variant::nil);
+                    let variant_b = body_b.map(|b| b.to_variant()).unwrap_or_else(Variant::nil);
+                    owner.emit_signal(signal, &[variant_a, variant_b]);
+                }
+            }
+
+            for event in physics.drain_contact_force_events() {
+                let (body_a, body_b) = Self::bodies_for_event(&physics, &event);
+                let body = body_a
+                    .and_then(|handle| self.node_for_body(handle))
+                    .or_else(|| body_b.and_then(|handle| self.node_for_body(handle)));
+
+                if let Some(body) = body {
+                    owner.emit_signal(
+                        "contact_force",
+                        &[body.to_variant(), (event.total_force_magnitude as f64).to_variant()],
+                    );
+                }
+
+                if self.effect_impulse_threshold > 0.
+                    && event.total_force_magnitude > self.effect_impulse_threshold
+                {
+                    if let Some(origin) = Self::contact_effect_origin(&physics, body_a, body_b, &event) {
+                        impact_effects.push(origin);
+                    }
+                }
+            }
+        }
+
+        // Spawned once `physics` is dropped: `spawn_effect` needs its own
+        // mutable borrow to create the effect's sensor body.
+        for (position, direction) in impact_effects {
+            self.spawn_effect(
+                owner,
+                position,
+                direction,
+                self.effect_frame_durations.clone(),
+                self.effect_lifetime,
+            );
+        }
+    }
+
+    fn bodies_for_event(
+        physics: &PhysicsState,
+        event: &ContactForceEvent,
+    ) -> (Option<RigidBodyHandle>, Option<RigidBodyHandle>) {
+        (
+            physics.collider_parent(event.collider1),
+            physics.collider_parent(event.collider2),
+        )
+    }
+
+    /// `ContactForceEvent` doesn't track the contact point itself, so it's
+    /// approximated as the midpoint of the two colliders' parent bodies.
+    fn contact_effect_origin(
+        physics: &PhysicsState,
+        body_a: Option<RigidBodyHandle>,
+        body_b: Option<RigidBodyHandle>,
+        event: &ContactForceEvent,
+    ) -> Option<(Vector2, Vector2)> {
+        let body_pos = |handle| physics.bodies.get(handle).map(|body| body.position().translation);
+
+        let pos_a = body_a.and_then(body_pos);
+        let pos_b = body_b.and_then(body_pos);
+
+        let position = match (pos_a, pos_b) {
+            (Some(a), Some(b)) => Vector2::new((a.x + b.x) / 2., (a.y + b.y) / 2.),
+            (Some(p), None) | (None, Some(p)) => Vector2::new(p.x, p.y),
+            (None, None) => return None,
+        };
+
+        let direction = Vector2::new(event.max_force_direction.x, event.max_force_direction.y);
+
+        Some((position, direction))
+    }
+
+    fn node_for_body(&self, handle: RigidBodyHandle) -> Option<Ref<Node2D>> {
+        self.boxes
+            .borrow()
+            .iter()
+            .flatten()
+            .find(|(body, _)| *body == handle)
+            .map(|(_, node)| *node)
+    }
+
+    /// The body handle of a spawned box, matched by Godot instance id since
+    /// `boxes` stores the node rather than its id directly.
+    fn body_for_node(&self, node: &Node2D) -> Option<RigidBodyHandle> {
+        let target = node.get_instance_id();
+
+        self.boxes
+            .borrow()
+            .iter()
+            .flatten()
+            .find(|(_, n)| unsafe { n.assume_safe() }.get_instance_id() == target)
+            .map(|(handle, _)| *handle)
+    }
+
+    /// Returns `-1` if either node isn't a spawned box.
+    fn add_joint(
+        &self,
+        node_a: Ref<Node2D>,
+        node_b: Ref<Node2D>,
+        build: impl FnOnce(&mut PhysicsState, RigidBodyHandle, RigidBodyHandle) -> JointHandle,
+    ) -> i64 {
+        let node_a = unsafe { node_a.assume_safe() };
+        let node_b = unsafe { node_b.assume_safe() };
+
+        let body_a = self.body_for_node(&node_a);
+        let body_b = self.body_for_node(&node_b);
+
+        let (body_a, body_b) = match (body_a, body_b) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return -1,
+        };
+
+        let handle = build(&mut self.physics.borrow_mut(), body_a, body_b);
+
+        let mut joints = self.joints.borrow_mut();
+        joints.push(Some(handle));
+
+        (joints.len() - 1) as i64
+    }
+
+    #[export]
+    fn add_revolute_joint(
+        &self,
+        _owner: &Node2D,
+        node_a: Ref<Node2D>,
+        node_b: Ref<Node2D>,
+        anchor_a: Vector2,
+        anchor_b: Vector2,
+    ) -> i64 {
+        self.add_joint(node_a, node_b, |physics, body_a, body_b| {
+            physics.add_revolute_joint(
+                body_a,
+                body_b,
+                na::Point2::new(anchor_a.x, anchor_a.y),
+                na::Point2::new(anchor_b.x, anchor_b.y),
+            )
+        })
+    }
+
+    #[export]
+    fn add_fixed_joint(
+        &self,
+        _owner: &Node2D,
+        node_a: Ref<Node2D>,
+        node_b: Ref<Node2D>,
+        anchor_a: Vector2,
+        anchor_b: Vector2,
+    ) -> i64 {
+        self.add_joint(node_a, node_b, |physics, body_a, body_b| {
+            physics.add_fixed_joint(
+                body_a,
+                body_b,
+                Isometry::translation(anchor_a.x, anchor_a.y),
+                Isometry::translation(anchor_b.x, anchor_b.y),
+            )
+        })
+    }
+
+    #[export]
+    fn add_prismatic_joint(
+        &self,
+        _owner: &Node2D,
+        node_a: Ref<Node2D>,
+        node_b: Ref<Node2D>,
+        anchor_a: Vector2,
+        axis_a: Vector2,
+        anchor_b: Vector2,
+        axis_b: Vector2,
+    ) -> i64 {
+        self.add_joint(node_a, node_b, |physics, body_a, body_b| {
+            physics.add_prismatic_joint(
+                body_a,
+                body_b,
+                na::Point2::new(anchor_a.x, anchor_a.y),
+                na::Unit::new_normalize(na::Vector2::new(axis_a.x, axis_a.y)),
+                na::Point2::new(anchor_b.x, anchor_b.y),
+                na::Unit::new_normalize(na::Vector2::new(axis_b.x, axis_b.y)),
+            )
+        })
+    }
+
+    /// A negative or already-removed `index` is a no-op.
+    #[export]
+    fn remove_joint(&self, _owner: &Node2D, index: i64) {
+        let mut joints = self.joints.borrow_mut();
+
+        if let Some(slot) = joints.get_mut(usize::try_from(index).unwrap_or(usize::MAX)) {
+            if let Some(handle) = slot.take() {
+                self.physics.borrow_mut().remove_joint(handle);
+            }
+        }
+    }
+
+    fn update_boxes(&self, _owner: &Node2D, factor: f32) {
+        let physics = self.physics.borrow();
+
+        for (handle, node) in self.boxes.borrow().iter().flatten() {
+            let handle = *handle;
+            let pos = physics
+                .render_position(handle, factor)
+                .unwrap_or_else(|| *physics.bodies.get(handle).unwrap().position());
 
             let node = unsafe { node.assume_safe() };
 
@@ -156,26 +877,429 @@ impl RapierWorld2D {
         }
     }
 
+    /// Despawns every box whose position has left `viewport_rect`.
+    fn cull_offscreen_boxes(&self, owner: &Node2D) {
+        let viewport_rect = match *self.viewport_rect.borrow() {
+            Some(rect) => rect,
+            None => return,
+        };
+
+        let out_of_bounds: Vec<i64> = {
+            let physics = self.physics.borrow();
+
+            self.boxes
+                .borrow()
+                .iter()
+                .enumerate()
+                .filter_map(|(index, slot)| {
+                    let (handle, _) = slot.as_ref()?;
+                    let pos = physics.bodies.get(*handle)?.position().translation;
+
+                    (!viewport_rect.has_point(Vector2::new(pos.x, pos.y))).then(|| index as i64)
+                })
+                .collect()
+        };
+
+        for index in out_of_bounds {
+            self.despawn(owner, index);
+        }
+    }
+
+    /// A negative or already-despawned `index` is a no-op.
+    #[export]
+    fn despawn(&self, _owner: &Node2D, index: i64) {
+        let mut boxes = self.boxes.borrow_mut();
+
+        let slot = match boxes.get_mut(usize::try_from(index).unwrap_or(usize::MAX)) {
+            Some(slot) => slot,
+            None => return,
+        };
+
+        if let Some((handle, node)) = slot.take() {
+            self.physics.borrow_mut().despawn(handle);
+
+            let node = unsafe { node.assume_safe() };
+            node.queue_free();
+        }
+    }
+
+    /// For rollback netplay: save at frame K, resimulate past it on
+    /// misprediction, `load_state` back to K and replay corrected inputs.
+    /// Deterministic under `Fixed`/`Interpolated` `timestep_mode` (fixed
+    /// `dt`, no wall-clock or RNG input). Effects are cosmetic and aren't
+    /// captured; `load_state` clears them instead.
+    #[export]
+    fn save_state(&self, _owner: &Node2D) -> ByteArray {
+        let physics = self.physics.borrow();
+        let boxes = self
+            .boxes
+            .borrow()
+            .iter()
+            .map(|slot| {
+                slot.map(|(handle, node)| {
+                    let node = unsafe { node.assume_safe() };
+                    (handle, node.name().to_string())
+                })
+            })
+            .collect();
+
+        let snapshot = WorldSnapshotRef {
+            physics: physics.snapshot(),
+            boxes,
+            joints: self.joints.borrow().clone(),
+        };
+        let bytes = bincode::serialize(&snapshot).expect("world state should always serialize");
+
+        ByteArray::from_vec(bytes)
+    }
+
+    /// Each box slot gets its snapshot handle back; boxes spawned since the
+    /// snapshot are freed, and boxes despawned since it get a fresh node
+    /// under their saved name. Live effects are freed outright, since their sensor bodies
+    /// don't exist in the restored `RigidBodySet`.
+    #[export]
+    fn load_state(&self, owner: &Node2D, bytes: ByteArray) {
+        let snapshot: WorldSnapshot = bincode::deserialize(&bytes.read())
+            .expect("bytes must come from RapierWorld2D::save_state");
+
+        for slot in self.effects.borrow_mut().drain(..) {
+            if let Some(effect) = slot {
+                unsafe { effect.node.assume_safe() }.queue_free();
+            }
+        }
+
+        self.physics.borrow_mut().restore(snapshot.physics);
+        *self.joints.borrow_mut() = snapshot.joints;
+
+        let mut boxes = self.boxes.borrow_mut();
+        for slot in boxes.drain(snapshot.boxes.len().min(boxes.len())..) {
+            if let Some((_, node)) = slot {
+                unsafe { node.assume_safe() }.queue_free();
+            }
+        }
+
+        for (i, entry) in snapshot.boxes.into_iter().enumerate() {
+            let previous = boxes.get(i).cloned().flatten();
+
+            match (entry, previous) {
+                (Some((handle, _)), Some((_, node))) => {
+                    boxes[i] = Some((handle, node));
+                }
+                (Some((handle, name)), None) => {
+                    // Despawning freed this box's node, so instance a fresh
+                    // one under the name saved in the snapshot.
+                    let box_asset = load_scene("res://scenes/RapierBox.tscn").unwrap();
+                    let new_node = instance_scene::<Node2D>(&box_asset);
+                    new_node.set_name(&name);
+
+                    let shared_node = new_node.into_shared();
+                    owner.add_child(shared_node, false);
+                    let slot = Some((handle, shared_node));
+
+                    if i < boxes.len() {
+                        boxes[i] = slot;
+                    } else {
+                        boxes.push(slot);
+                    }
+                }
+                (None, Some((_, node))) => {
+                    unsafe { node.assume_safe() }.queue_free();
+                    boxes[i] = None;
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
     fn spawn(&self, owner: &Node2D, x: f32, y: f32) {
-        let mut physics = self.physics.borrow_mut();
-        let handle = physics.add_box(x, y);
+        let threshold = (self.contact_force_threshold > 0.).then(|| self.contact_force_threshold);
+        let shape = Shape::Cuboid {
+            half_extents: na::Vector2::new(48. * 0.4, 48. * 0.4),
+        };
+
+        let handle =
+            self.physics
+                .borrow_mut()
+                .spawn_body(x, y, shape, BodyMaterial::default(), threshold);
 
+        if let Some(handle) = handle {
+            self.track_box(owner, handle);
+        }
+    }
+
+    /// Spawns a ball-shaped body. Returns its box index.
+    #[export]
+    fn spawn_ball(
+        &self,
+        owner: &Node2D,
+        x: f32,
+        y: f32,
+        radius: f32,
+        material: BodyMaterial,
+        contact_force_threshold: f32,
+    ) -> i64 {
+        self.spawn_shape(
+            owner,
+            x,
+            y,
+            Shape::Ball { radius },
+            material,
+            contact_force_threshold,
+        )
+    }
+
+    /// Spawns a cuboid-shaped body, `half_extents` given in Rapier units.
+    #[export]
+    fn spawn_cuboid(
+        &self,
+        owner: &Node2D,
+        x: f32,
+        y: f32,
+        half_extents: Vector2,
+        material: BodyMaterial,
+        contact_force_threshold: f32,
+    ) -> i64 {
+        let shape = Shape::Cuboid {
+            half_extents: na::Vector2::new(half_extents.x, half_extents.y),
+        };
+
+        self.spawn_shape(owner, x, y, shape, material, contact_force_threshold)
+    }
+
+    /// Spawns a capsule-shaped body standing along the local Y axis.
+    #[export]
+    fn spawn_capsule(
+        &self,
+        owner: &Node2D,
+        x: f32,
+        y: f32,
+        half_height: f32,
+        radius: f32,
+        material: BodyMaterial,
+        contact_force_threshold: f32,
+    ) -> i64 {
+        let shape = Shape::Capsule {
+            half_height,
+            radius,
+        };
+
+        self.spawn_shape(owner, x, y, shape, material, contact_force_threshold)
+    }
+
+    /// Spawns a body whose collider is the convex hull of `points`. Returns
+    /// `-1` if `points` doesn't describe a valid hull (e.g. too few points).
+    #[export]
+    fn spawn_polygon(
+        &self,
+        owner: &Node2D,
+        x: f32,
+        y: f32,
+        points: Vector2Array,
+        material: BodyMaterial,
+        contact_force_threshold: f32,
+    ) -> i64 {
+        let points = points
+            .read()
+            .iter()
+            .map(|p| na::Point2::new(p.x, p.y))
+            .collect();
+
+        self.spawn_shape(
+            owner,
+            x,
+            y,
+            Shape::ConvexPolygon { points },
+            material,
+            contact_force_threshold,
+        )
+    }
+
+    fn spawn_shape(
+        &self,
+        owner: &Node2D,
+        x: f32,
+        y: f32,
+        shape: Shape,
+        material: BodyMaterial,
+        contact_force_threshold: f32,
+    ) -> i64 {
+        let threshold = (contact_force_threshold > 0.).then(|| contact_force_threshold);
+        let handle = self
+            .physics
+            .borrow_mut()
+            .spawn_body(x, y, shape, material, threshold);
+
+        match handle {
+            Some(handle) => self.track_box(owner, handle),
+            None => -1,
+        }
+    }
+
+    /// Spawns an impact effect at `position`, carried along `direction`
+    /// randomized within the `effect_spread_degrees` cone.
+    #[export]
+    fn spawn_effect(
+        &self,
+        owner: &Node2D,
+        position: Vector2,
+        direction: Vector2,
+        frame_durations: Float32Array,
+        lifetime: f32,
+    ) -> i64 {
+        let frame_durations = frame_durations.read().to_vec();
+        let frame_durations = if frame_durations.is_empty() {
+            vec![lifetime.max(f32::EPSILON)]
+        } else {
+            frame_durations
+        };
+
+        let spread = self.effect_spread_degrees.max(0.).to_radians();
+        let base_angle = direction.y.atan2(direction.x);
+
+        let mut rng = rand::thread_rng();
+        let angle = base_angle + rng.gen_range(-spread..=spread);
+        let (speed_min, speed_max) = (
+            self.effect_speed_min.min(self.effect_speed_max),
+            self.effect_speed_min.max(self.effect_speed_max),
+        );
+        let speed = if speed_max - speed_min > f32::EPSILON {
+            rng.gen_range(speed_min..speed_max)
+        } else {
+            speed_min
+        };
+        let angvel = rng.gen_range(-spread..=spread);
+
+        let linvel = na::Vector2::new(angle.cos(), angle.sin()) * speed;
+
+        let handle = self
+            .physics
+            .borrow_mut()
+            .spawn_effect_body(position.x, position.y, linvel, angvel);
+
+        let effect_asset = load_scene("res://scenes/RapierEffect.tscn").unwrap();
+        let new_node = instance_scene::<Sprite>(&effect_asset).into_shared();
+        owner.add_child(new_node, false);
+
+        let effect = Effect {
+            body: handle,
+            node: new_node,
+            frame_durations,
+            frame_index: 0,
+            frame_elapsed: 0.,
+            age: 0.,
+            lifetime,
+        };
+
+        // Effects are auto-spawned continuously (unlike boxes, which GDScript
+        // spawns deliberately), so reuse a despawned slot when one is free
+        // instead of growing `effects` without bound over a long session.
+        let mut effects = self.effects.borrow_mut();
+        let index = match effects.iter().position(|slot| slot.is_none()) {
+            Some(index) => {
+                effects[index] = Some(effect);
+                index
+            }
+            None => {
+                effects.push(Some(effect));
+                effects.len() - 1
+            }
+        };
+
+        index as i64
+    }
+
+    /// Despawns each effect once its `lifetime` has elapsed.
+    fn tick_effects(&self, delta: f32) {
+        let physics = self.physics.borrow();
+        let mut expired = Vec::new();
+
+        for (index, slot) in self.effects.borrow_mut().iter_mut().enumerate() {
+            let effect = match slot {
+                Some(effect) => effect,
+                None => continue,
+            };
+
+            effect.age += delta;
+            if effect.age >= effect.lifetime {
+                expired.push(index);
+                continue;
+            }
+
+            if let Some(duration) = effect.frame_durations.get(effect.frame_index) {
+                effect.frame_elapsed += delta;
+                if effect.frame_elapsed >= *duration
+                    && effect.frame_index + 1 < effect.frame_durations.len()
+                {
+                    effect.frame_index += 1;
+                    effect.frame_elapsed = 0.;
+                }
+            }
+
+            if let Some(body) = physics.bodies.get(effect.body) {
+                let pos = body.position();
+                let node = unsafe { effect.node.assume_safe() };
+
+                node.set_position(Vector2::new(pos.translation.x, pos.translation.y));
+                node.set_rotation(pos.rotation.angle() as f64);
+                node.set_frame(effect.frame_index as i64);
+
+                let mut color = node.modulate();
+                color.a = (1. - effect.age / effect.lifetime).clamp(0., 1.);
+                node.set_modulate(color);
+            }
+        }
+
+        drop(physics);
+
+        for index in expired {
+            self.despawn_effect(index);
+        }
+    }
+
+    /// An already-despawned `index` is a no-op.
+    fn despawn_effect(&self, index: usize) {
+        let mut effects = self.effects.borrow_mut();
+
+        let slot = match effects.get_mut(index) {
+            Some(slot) => slot,
+            None => return,
+        };
+
+        if let Some(effect) = slot.take() {
+            self.physics.borrow_mut().despawn(effect.body);
+            unsafe { effect.node.assume_safe() }.queue_free();
+        }
+    }
+
+    /// Instances `RapierBox.tscn`, adds it under `owner` and tracks it
+    /// alongside `handle` in `boxes`. Returns the new box's index.
+    fn track_box(&self, owner: &Node2D, handle: RigidBodyHandle) -> i64 {
+        // Reuse a slot freed by `despawn`/`cull_offscreen_boxes` when one is
+        // free, instead of growing `boxes` without bound over a long
+        // auto-cull session (mirrors `spawn_effect`'s slot reuse).
         let mut boxes = self.boxes.borrow_mut();
-        let falling_box_index = boxes.len();
+        let falling_box_index = match boxes.iter().position(Option::is_none) {
+            Some(index) => index,
+            None => boxes.len(),
+        };
 
-        {
-            let box_asset = load_scene("res://scenes/RapierBox.tscn").unwrap();
+        let box_asset = load_scene("res://scenes/RapierBox.tscn").unwrap();
 
-            let new_node = instance_scene::<Node2D>(&box_asset);
+        let new_node = instance_scene::<Node2D>(&box_asset);
 
-            let key_str = format!("box_{}", falling_box_index);
-            new_node.set_name(&key_str);
+        let key_str = format!("box_{}", falling_box_index);
+        new_node.set_name(&key_str);
 
-            let shared_node = new_node.into_shared();
-            owner.add_child(shared_node, false);
+        let shared_node = new_node.into_shared();
+        owner.add_child(shared_node, false);
 
-            boxes.push((handle, shared_node));
+        if falling_box_index < boxes.len() {
+            boxes[falling_box_index] = Some((handle, shared_node));
+        } else {
+            boxes.push(Some((handle, shared_node)));
         }
+
+        falling_box_index as i64
     }
 }
 
@@ -198,3 +1322,50 @@ where
 
     instance.try_cast::<Root>().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The invariant rollback netplay depends on: save at frame K, simulate
+    /// to K+10, restore K, simulate 10 more steps again, and get the exact
+    /// same body position either way.
+    #[test]
+    fn save_state_restore_is_deterministic() {
+        let mut physics = PhysicsState::new();
+        let gravity = Vector2::new(0., 98.);
+        let dt = 1. / 60.;
+
+        physics.add_static(0., 600., 800., 10.);
+        let body = physics
+            .spawn_body(
+                400.,
+                0.,
+                Shape::Ball { radius: 20. },
+                BodyMaterial::default(),
+                None,
+            )
+            .unwrap();
+
+        for _ in 0..10 {
+            physics.step(gravity, dt);
+        }
+
+        let snapshot = bincode::serialize(&physics.snapshot()).unwrap();
+
+        for _ in 0..10 {
+            physics.step(gravity, dt);
+        }
+        let diverged = *physics.bodies.get(body).unwrap().position();
+
+        let restored: PhysicsSnapshot = bincode::deserialize(&snapshot).unwrap();
+        physics.restore(restored);
+
+        for _ in 0..10 {
+            physics.step(gravity, dt);
+        }
+        let replayed = *physics.bodies.get(body).unwrap().position();
+
+        assert_eq!(diverged.translation.vector, replayed.translation.vector);
+    }
+}